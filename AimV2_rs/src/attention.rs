@@ -0,0 +1,125 @@
+use tch::{nn, nn::ModuleT, Kind, Tensor};
+
+use crate::config::AIMv2Config;
+
+/// Multi-head self-attention used inside each `AIMv2Block`.
+#[derive(Debug)]
+pub struct AIMv2Attention {
+    qkv: nn::Linear,
+    proj: nn::Linear,
+    num_heads: i64,
+    head_dim: i64,
+    quiet_softmax: bool,
+}
+
+impl AIMv2Attention {
+    pub fn new(vs: &nn::Path, config: &AIMv2Config) -> Self {
+        let dim = config.hidden_size;
+        let num_heads = config.num_attention_heads;
+        let head_dim = dim / num_heads;
+
+        let qkv_cfg = nn::LinearConfig {
+            bias: config.qkv_bias,
+            ..Default::default()
+        };
+        let proj_cfg = nn::LinearConfig {
+            bias: config.use_bias,
+            ..Default::default()
+        };
+
+        let qkv = nn::linear(vs / "qkv", dim, dim * 3, qkv_cfg);
+        let proj = nn::linear(vs / "proj", dim, dim, proj_cfg);
+
+        Self {
+            qkv,
+            proj,
+            num_heads,
+            head_dim,
+            quiet_softmax: config.quiet_softmax,
+        }
+    }
+
+    /// Softmax-off-by-one ("quiet attention"): appends a virtual zero logit
+    /// to the denominator, so a row can sum to less than one. `m` folds that
+    /// virtual logit into the running max used for numerical stability.
+    fn softmax1(scores: &Tensor) -> Tensor {
+        let m = scores.amax(-1, true).clamp_min(0.0);
+        let numerator = (scores - &m).exp();
+        let denominator = (-&m).exp() + numerator.sum_dim_intlist(-1, true, Kind::Float);
+        numerator / denominator
+    }
+}
+
+impl AIMv2Attention {
+    /// Like `forward_t`, but optionally also returns the post-softmax
+    /// attention probabilities (`[batch, num_heads, seq_len, seq_len]`),
+    /// for callers probing the model via `forward_features`.
+    pub fn forward_t_with_attn(
+        &self,
+        xs: &Tensor,
+        _train: bool,
+        want_attn: bool,
+    ) -> (Tensor, Option<Tensor>) {
+        let (batch, seq_len, dim) = xs.size3().unwrap();
+
+        let qkv = tch::nn::Module::forward(&self.qkv, xs).reshape(&[
+            batch,
+            seq_len,
+            3,
+            self.num_heads,
+            self.head_dim,
+        ]);
+        let qkv = qkv.permute(&[2, 0, 3, 1, 4]);
+        let q = qkv.select(0, 0);
+        let k = qkv.select(0, 1);
+        let v = qkv.select(0, 2);
+
+        let scale = (self.head_dim as f64).powf(-0.5);
+        let scores = q.matmul(&k.transpose(-2, -1)) * scale;
+        let attn = if self.quiet_softmax {
+            Self::softmax1(&scores)
+        } else {
+            scores.softmax(-1, Kind::Float)
+        };
+
+        let out = attn
+            .matmul(&v)
+            .transpose(1, 2)
+            .contiguous()
+            .reshape(&[batch, seq_len, dim]);
+
+        let out = tch::nn::Module::forward(&self.proj, &out);
+        let attn = want_attn.then(|| attn.shallow_clone());
+        (out, attn)
+    }
+}
+
+impl ModuleT for AIMv2Attention {
+    fn forward_t(&self, xs: &Tensor, train: bool) -> Tensor {
+        self.forward_t_with_attn(xs, train, false).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hand-computed against logits `[1, 2, 3]`: `m = max(3, 0) = 3`,
+    /// numerator = `exp([-2, -1, 0])`, denominator additionally folds in
+    /// `exp(-3)` for the virtual zero logit — so the row sums to < 1, unlike
+    /// ordinary softmax.
+    #[test]
+    fn softmax1_matches_hand_computed_value() {
+        let scores = Tensor::from_slice(&[1.0f32, 2.0, 3.0]).view([1, 3]);
+        let attn = AIMv2Attention::softmax1(&scores);
+
+        let expected = [0.0871443, 0.2368828, 0.6439143];
+        let mut sum = 0.0;
+        for (i, &want) in expected.iter().enumerate() {
+            let got = attn.double_value(&[0, i as i64]);
+            assert!((got - want).abs() < 1e-5, "index {i}: got {got}, want {want}");
+            sum += got;
+        }
+        assert!(sum < 1.0, "softmax-off-by-one row should sum to less than 1, got {sum}");
+    }
+}
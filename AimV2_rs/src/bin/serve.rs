@@ -0,0 +1,148 @@
+use std::io::Read;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tch::nn::{self, ModuleT};
+use tch::Tensor;
+use tiny_http::{Method, Response, Server};
+
+use aimv2_model_rs::config::AIMv2Config;
+use aimv2_model_rs::image_transform::load_image_bytes;
+use aimv2_model_rs::preprocessor::AIMv2ViTPreprocessor;
+use aimv2_model_rs::transformer::AIMv2Transformer;
+use aimv2_model_rs::utils::auto_device;
+
+#[derive(Debug, Deserialize)]
+struct EmbedRequest {
+    /// Base64-encoded image bytes, one entry per image in the batch.
+    images: Vec<String>,
+    /// L2-normalize the pooled embeddings before returning them.
+    #[serde(default)]
+    normalize: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// The frozen model plus the config needed to preprocess incoming images.
+struct EmbeddingService {
+    config: AIMv2Config,
+    preprocessor: AIMv2ViTPreprocessor,
+    trunk: AIMv2Transformer,
+    device: tch::Device,
+}
+
+impl EmbeddingService {
+    fn load(weights_path: &str, config: AIMv2Config) -> Result<Self> {
+        let device = auto_device();
+        let mut vs = nn::VarStore::new(device);
+        let preprocessor = AIMv2ViTPreprocessor::new(&(vs.root() / "preprocessor"), &config);
+        let trunk = AIMv2Transformer::new(&(vs.root() / "trunk"), &config);
+        vs.load(weights_path)
+            .with_context(|| format!("Failed to load weights from '{}'", weights_path))?;
+        vs.freeze();
+
+        Ok(Self {
+            config,
+            preprocessor,
+            trunk,
+            device,
+        })
+    }
+
+    /// Decodes, batches and embeds a set of images, returning one pooled
+    /// (optionally L2-normalized) embedding per image.
+    fn embed(&self, images: &[String], normalize: bool) -> Result<Vec<Vec<f32>>> {
+        let tensors: Vec<Tensor> = images
+            .iter()
+            .map(|b64| {
+                let bytes = base64_decode(b64).context("Invalid base64 image payload")?;
+                load_image_bytes(&bytes, &self.config)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let batch = Tensor::cat(&tensors, 0).to_device(self.device);
+
+        let pooled = tch::no_grad(|| -> Tensor {
+            use tch::nn::Module;
+            let tokens = self.preprocessor.forward(&batch);
+            let hidden = self.trunk.forward_t(&tokens, false);
+            let pooled = hidden.mean_dim(1, false, hidden.kind());
+            if normalize {
+                &pooled / pooled.norm_scalaropt_dim(2, &[1], true)
+            } else {
+                pooled
+            }
+        });
+
+        let pooled = pooled.to_device(tch::Device::Cpu);
+        let hidden_size = self.config.hidden_size as usize;
+        let batch_size = images.len();
+        let flat: Vec<f32> = Vec::<f32>::try_from(pooled.reshape(&[-1]))?;
+        Ok(flat
+            .chunks(hidden_size)
+            .take(batch_size)
+            .map(|chunk| chunk.to_vec())
+            .collect())
+    }
+}
+
+fn base64_decode(input: &str) -> Result<Vec<u8>> {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD
+        .decode(input)
+        .context("base64 decode failed")
+}
+
+fn main() -> Result<()> {
+    let weights_path = "/Users/andrewmayes/Dev/AIMv2-rs/model/model.safetensors";
+    let listen_addr = "127.0.0.1:8080";
+
+    let config = AIMv2Config::aimv2_large_patch14();
+    let service = Arc::new(EmbeddingService::load(weights_path, config)?);
+
+    let server = Server::http(listen_addr)
+        .map_err(|err| anyhow::anyhow!("Failed to bind {}: {}", listen_addr, err))?;
+    println!("AIMv2 embedding server listening on http://{}", listen_addr);
+
+    for mut request in server.incoming_requests() {
+        if request.method() != &Method::Post || request.url() != "/embed" {
+            let response = Response::from_string("not found").with_status_code(404);
+            let _ = request.respond(response);
+            continue;
+        }
+
+        let mut body = String::new();
+        if let Err(err) = request.as_reader().read_to_string(&mut body) {
+            let response = Response::from_string(format!("bad request body: {err}"))
+                .with_status_code(400);
+            let _ = request.respond(response);
+            continue;
+        }
+
+        let result = serde_json::from_str::<EmbedRequest>(&body)
+            .context("Invalid JSON body")
+            .and_then(|req| {
+                service
+                    .embed(&req.images, req.normalize)
+                    .map(|embeddings| EmbedResponse { embeddings })
+            });
+
+        match result {
+            Ok(resp) => {
+                let body = serde_json::to_string(&resp)?;
+                let _ = request.respond(Response::from_string(body));
+            }
+            Err(err) => {
+                let response =
+                    Response::from_string(format!("error: {err:#}")).with_status_code(400);
+                let _ = request.respond(response);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use tch::{nn, nn::Module, nn::ModuleT, Tensor};
+
+use crate::attention::AIMv2Attention;
+use crate::config::AIMv2Config;
+use crate::ffn::AIMv2SwiGLUFFN;
+use crate::norm::RMSNorm;
+
+/// Transformer Block for AIMv2.
+#[derive(Debug)]
+pub struct AIMv2Block {
+    attn: AIMv2Attention,
+    norm_1: RMSNorm,
+    mlp: AIMv2SwiGLUFFN,
+    norm_2: RMSNorm,
+}
+
+impl AIMv2Block {
+    pub fn new(vs: &nn::Path, config: &AIMv2Config) -> Self {
+        let dim = config.hidden_size;
+        let eps = config.rms_norm_eps;
+
+        let attn = AIMv2Attention::new(&(vs / "attn"), config);
+        let norm_1 = RMSNorm::new(&(vs / "norm_1"), dim, eps);
+        let mlp = AIMv2SwiGLUFFN::new(&(vs / "mlp"), config);
+        let norm_2 = RMSNorm::new(&(vs / "norm_2"), dim, eps);
+
+        Self {
+            attn,
+            norm_1,
+            mlp,
+            norm_2,
+        }
+    }
+}
+
+impl AIMv2Block {
+    /// Like `forward_t`, but optionally also returns this block's attention
+    /// probabilities, for `AIMv2Transformer::forward_features`.
+    pub fn forward_t_with_attn(
+        &self,
+        xs: &Tensor,
+        train: bool,
+        want_attn: bool,
+    ) -> (Tensor, Option<Tensor>) {
+        let (attn_out, attn) = self
+            .attn
+            .forward_t_with_attn(&self.norm_1.forward(xs), train, want_attn);
+        let residual_1 = xs + attn_out;
+        let out = residual_1.shallow_clone() + self.mlp.forward(&self.norm_2.forward(&residual_1));
+        (out, attn)
+    }
+}
+
+impl ModuleT for AIMv2Block {
+    fn forward_t(&self, xs: &Tensor, train: bool) -> Tensor {
+        self.forward_t_with_attn(xs, train, false).0
+    }
+}
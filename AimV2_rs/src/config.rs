@@ -0,0 +1,225 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// Registry of the published AIMv2 checkpoints, so callers can pick a
+/// variant without having to know its hyperparameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AIMv2Variant {
+    Large,
+    Huge,
+    ThreeB,
+}
+
+/// Patch size for a registry preset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AIMv2PatchSize {
+    Patch14,
+    Patch16,
+}
+
+impl AIMv2PatchSize {
+    fn as_i64(self) -> i64 {
+        match self {
+            AIMv2PatchSize::Patch14 => 14,
+            AIMv2PatchSize::Patch16 => 16,
+        }
+    }
+}
+
+/// Resolution for a registry preset: either a fixed square crop size, or
+/// the `-native` checkpoints' default resolution (224). This port still
+/// patchifies to one fixed square crop either way — genuine variable
+/// per-image resolution would need interpolated position embeddings, which
+/// `AIMv2PositionalEmbedding` doesn't do — so `Native` just selects that
+/// default crop size instead of requiring the caller to know it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AIMv2Resolution {
+    Fixed(i64),
+    Native,
+}
+
+/// Configuration for an AIMv2 model.
+///
+/// Mirrors the Hugging Face `AIMv2Config` fields needed to build the patch
+/// embedding, positional embedding and transformer trunk.
+#[derive(Debug, Clone)]
+pub struct AIMv2Config {
+    pub hidden_size: i64,
+    pub num_hidden_layers: i64,
+    pub num_attention_heads: i64,
+    pub intermediate_size: i64,
+    pub num_channels: i64,
+    pub image_size: i64,
+    pub patch_size: i64,
+    pub rms_norm_eps: f64,
+    pub qkv_bias: bool,
+    pub use_bias: bool,
+    /// When set, `AIMv2Attention` normalizes with softmax-off-by-one
+    /// ("quiet attention") instead of standard softmax, letting a query
+    /// attend to nothing and reducing activation outliers.
+    pub quiet_softmax: bool,
+    /// Per-channel mean/std used by `image_transform::load_image`, matching
+    /// the Hugging Face `ViTImageProcessor` the checkpoint was trained with.
+    pub image_mean: [f64; 3],
+    pub image_std: [f64; 3],
+}
+
+impl AIMv2Config {
+    /// Configuration for `apple/aimv2-large-patch14-224`.
+    pub fn aimv2_large_patch14() -> Self {
+        Self {
+            hidden_size: 1024,
+            num_hidden_layers: 24,
+            num_attention_heads: 8,
+            intermediate_size: 2816,
+            num_channels: 3,
+            image_size: 224,
+            patch_size: 14,
+            rms_norm_eps: 1e-5,
+            qkv_bias: false,
+            use_bias: false,
+            quiet_softmax: false,
+            image_mean: [0.5, 0.5, 0.5],
+            image_std: [0.5, 0.5, 0.5],
+        }
+    }
+
+    /// Configuration for `apple/aimv2-huge-patch14-224`.
+    pub fn aimv2_huge_patch14() -> Self {
+        Self {
+            hidden_size: 1536,
+            num_hidden_layers: 24,
+            num_attention_heads: 12,
+            intermediate_size: 4096,
+            ..Self::aimv2_large_patch14()
+        }
+    }
+
+    /// Configuration for `apple/aimv2-3B-patch14-224`.
+    pub fn aimv2_3b_patch14() -> Self {
+        Self {
+            hidden_size: 3072,
+            num_hidden_layers: 24,
+            num_attention_heads: 24,
+            intermediate_size: 8192,
+            ..Self::aimv2_large_patch14()
+        }
+    }
+
+    /// Looks up the preset for a registry variant at the given patch size
+    /// and resolution.
+    pub fn from_variant(
+        variant: AIMv2Variant,
+        patch_size: AIMv2PatchSize,
+        resolution: AIMv2Resolution,
+    ) -> Self {
+        let mut config = match variant {
+            AIMv2Variant::Large => Self::aimv2_large_patch14(),
+            AIMv2Variant::Huge => Self::aimv2_huge_patch14(),
+            AIMv2Variant::ThreeB => Self::aimv2_3b_patch14(),
+        };
+        config.patch_size = patch_size.as_i64();
+        config.image_size = match resolution {
+            AIMv2Resolution::Fixed(image_size) => image_size,
+            // The published `-native` checkpoints are trained at 224; this
+            // port still needs one concrete crop size up front.
+            AIMv2Resolution::Native => 224,
+        };
+        config
+    }
+
+    /// Builds a config from a Hugging Face `config.json`, so a different
+    /// checkpoint can be run without editing source.
+    pub fn from_pretrained(dir: impl AsRef<Path>) -> Result<Self> {
+        let config_path = dir.as_ref().join("config.json");
+        let raw = std::fs::read_to_string(&config_path)
+            .with_context(|| format!("Failed to read {:?}", config_path))?;
+        let hf_config: HfConfigJson = serde_json::from_str(&raw)
+            .with_context(|| format!("Failed to parse {:?}", config_path))?;
+        Ok(hf_config.into_config())
+    }
+
+    /// Number of patches along one side of the image.
+    pub fn num_patches_per_side(&self) -> i64 {
+        self.image_size / self.patch_size
+    }
+
+    /// Total number of patch tokens produced by the patch embedding.
+    pub fn num_patches(&self) -> i64 {
+        self.num_patches_per_side() * self.num_patches_per_side()
+    }
+}
+
+/// Mirrors the subset of a Hugging Face AIMv2 `config.json` needed to build
+/// an `AIMv2Config`; unrecognized fields are ignored and missing fields fall
+/// back to the `aimv2-large-patch14` defaults.
+#[derive(Debug, Deserialize)]
+struct HfConfigJson {
+    hidden_size: Option<i64>,
+    num_hidden_layers: Option<i64>,
+    num_attention_heads: Option<i64>,
+    intermediate_size: Option<i64>,
+    num_channels: Option<i64>,
+    image_size: Option<i64>,
+    patch_size: Option<i64>,
+    rms_norm_eps: Option<f64>,
+    qkv_bias: Option<bool>,
+    use_bias: Option<bool>,
+    image_mean: Option<[f64; 3]>,
+    image_std: Option<[f64; 3]>,
+}
+
+impl HfConfigJson {
+    fn into_config(self) -> AIMv2Config {
+        let defaults = AIMv2Config::aimv2_large_patch14();
+        AIMv2Config {
+            hidden_size: self.hidden_size.unwrap_or(defaults.hidden_size),
+            num_hidden_layers: self.num_hidden_layers.unwrap_or(defaults.num_hidden_layers),
+            num_attention_heads: self
+                .num_attention_heads
+                .unwrap_or(defaults.num_attention_heads),
+            intermediate_size: self.intermediate_size.unwrap_or(defaults.intermediate_size),
+            num_channels: self.num_channels.unwrap_or(defaults.num_channels),
+            image_size: self.image_size.unwrap_or(defaults.image_size),
+            patch_size: self.patch_size.unwrap_or(defaults.patch_size),
+            rms_norm_eps: self.rms_norm_eps.unwrap_or(defaults.rms_norm_eps),
+            qkv_bias: self.qkv_bias.unwrap_or(defaults.qkv_bias),
+            use_bias: self.use_bias.unwrap_or(defaults.use_bias),
+            image_mean: self.image_mean.unwrap_or(defaults.image_mean),
+            image_std: self.image_std.unwrap_or(defaults.image_std),
+            ..defaults
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fields present in `config.json` should override the
+    /// `aimv2-large-patch14` defaults; fields absent from the file (here,
+    /// everything but `hidden_size`/`patch_size`/`image_mean`) should fall
+    /// back to them.
+    #[test]
+    fn from_pretrained_overrides_present_fields_and_defaults_the_rest() {
+        let dir = std::env::temp_dir().join(format!("aimv2_rs_config_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("config.json"),
+            r#"{"hidden_size": 2048, "patch_size": 16, "image_mean": [0.1, 0.2, 0.3]}"#,
+        )
+        .unwrap();
+
+        let config = AIMv2Config::from_pretrained(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let defaults = AIMv2Config::aimv2_large_patch14();
+        assert_eq!(config.hidden_size, 2048);
+        assert_eq!(config.patch_size, 16);
+        assert_eq!(config.image_mean, [0.1, 0.2, 0.3]);
+        assert_eq!(config.num_hidden_layers, defaults.num_hidden_layers);
+        assert_eq!(config.image_std, defaults.image_std);
+    }
+}
@@ -0,0 +1,28 @@
+use tch::{nn, nn::Module, Tensor};
+
+use crate::config::AIMv2Config;
+
+/// Learned positional embedding added to patch tokens.
+#[derive(Debug)]
+pub struct AIMv2PositionalEmbedding {
+    pos_embed: Tensor,
+}
+
+impl AIMv2PositionalEmbedding {
+    pub fn new(vs: &nn::Path, config: &AIMv2Config) -> Self {
+        let pos_embed = vs.randn(
+            "pos_embed",
+            &[1, config.num_patches(), config.hidden_size],
+            0.0,
+            0.02,
+        );
+        Self { pos_embed }
+    }
+}
+
+impl Module for AIMv2PositionalEmbedding {
+    fn forward(&self, xs: &Tensor) -> Tensor {
+        let seq_len = xs.size()[1];
+        xs + self.pos_embed.narrow(1, 0, seq_len)
+    }
+}
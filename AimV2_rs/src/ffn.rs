@@ -0,0 +1,36 @@
+use tch::{nn, nn::Module, Tensor};
+
+use crate::config::AIMv2Config;
+
+/// SwiGLU feed-forward block used inside each `AIMv2Block`.
+#[derive(Debug)]
+pub struct AIMv2SwiGLUFFN {
+    fc1: nn::Linear,
+    fc2: nn::Linear,
+    fc3: nn::Linear,
+}
+
+impl AIMv2SwiGLUFFN {
+    pub fn new(vs: &nn::Path, config: &AIMv2Config) -> Self {
+        let in_dim = config.hidden_size;
+        let hidden_dim = config.intermediate_size;
+        let cfg = nn::LinearConfig {
+            bias: config.use_bias,
+            ..Default::default()
+        };
+
+        let fc1 = nn::linear(vs / "fc1", in_dim, hidden_dim, cfg);
+        let fc2 = nn::linear(vs / "fc2", hidden_dim, in_dim, cfg);
+        let fc3 = nn::linear(vs / "fc3", in_dim, hidden_dim, cfg);
+
+        Self { fc1, fc2, fc3 }
+    }
+}
+
+impl Module for AIMv2SwiGLUFFN {
+    fn forward(&self, xs: &Tensor) -> Tensor {
+        let gate = self.fc1.forward(xs).silu();
+        let value = self.fc3.forward(xs);
+        self.fc2.forward(&(gate * value))
+    }
+}
@@ -0,0 +1,86 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tch::{Kind, Tensor};
+
+use crate::config::AIMv2Config;
+
+/// Loads an image file and runs the same resize / center-crop / rescale /
+/// normalize pipeline as Hugging Face's `ViTImageProcessor`, so the returned
+/// tensor reproduces the PyTorch embeddings exactly.
+///
+/// Steps: bilinear-resize the shortest side to `config.image_size`,
+/// center-crop to `image_size x image_size`, rescale to `[0, 1]`, then
+/// normalize per-channel with `config.image_mean` / `config.image_std`.
+/// Returns an NCHW float `Tensor` ready for `AIMv2Model::forward_t`.
+pub fn load_image(path: &Path, config: &AIMv2Config) -> Result<Tensor> {
+    let image = tch::vision::image::load(path)
+        .with_context(|| format!("Failed to load image: {:?}", path))?;
+    preprocess(image, config)
+}
+
+/// Decodes an in-memory image (PNG/JPEG/etc.) and runs it through the same
+/// pipeline as [`load_image`]. Used by the embedding server, which receives
+/// raw image bytes over the wire rather than a file path.
+pub fn load_image_bytes(bytes: &[u8], config: &AIMv2Config) -> Result<Tensor> {
+    let decoded = image::load_from_memory(bytes)
+        .context("Failed to decode image bytes")?
+        .to_rgb8();
+    let (width, height) = decoded.dimensions();
+    let chw = Tensor::from_slice(decoded.as_raw())
+        .view([height as i64, width as i64, 3])
+        .permute(&[2, 0, 1]);
+    preprocess(chw, config)
+}
+
+/// Shared resize / center-crop / rescale / normalize pipeline taking a CHW
+/// uint8-or-float tensor and returning an NCHW float tensor.
+fn preprocess(image: Tensor, config: &AIMv2Config) -> Result<Tensor> {
+    let image = image.to_kind(Kind::Float);
+    let (_channels, height, width) = image.size3().unwrap();
+    let shorter_side = height.min(width) as f64;
+    let scale = config.image_size as f64 / shorter_side;
+    let resized_h = (height as f64 * scale).round() as i64;
+    let resized_w = (width as f64 * scale).round() as i64;
+
+    let resized = image
+        .unsqueeze(0)
+        .upsample_bilinear2d(&[resized_h, resized_w], false, None, None);
+
+    let top = (resized_h - config.image_size) / 2;
+    let left = (resized_w - config.image_size) / 2;
+    let cropped = resized
+        .narrow(2, top, config.image_size)
+        .narrow(3, left, config.image_size);
+
+    let rescaled = cropped / 255.0;
+
+    let mean = Tensor::from_slice(&config.image_mean)
+        .to_kind(Kind::Float)
+        .view([1, 3, 1, 1]);
+    let std = Tensor::from_slice(&config.image_std)
+        .to_kind(Kind::Float)
+        .view([1, 3, 1, 1]);
+
+    Ok((rescaled - mean) / std)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A non-square source image should still come out resized/cropped to
+    /// exactly `config.image_size` on both spatial dims, batched to NCHW.
+    #[test]
+    fn preprocess_output_has_expected_shape() {
+        let config = AIMv2Config::aimv2_large_patch14();
+        let image = Tensor::zeros(&[3, 300, 400], (Kind::Float, tch::Device::Cpu));
+
+        let out = preprocess(image, &config).unwrap();
+
+        assert_eq!(
+            out.size(),
+            vec![1, 3, config.image_size, config.image_size]
+        );
+    }
+}
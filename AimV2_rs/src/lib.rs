@@ -11,6 +11,9 @@ pub mod lib {
     pub mod attention;
     pub mod block;
     pub mod transformer;
+    pub mod quantized_transformer;
+    pub mod loader;
+    pub mod image_transform;
 }
 
 // Optional: Re-export if you want to use them directly without the lib:: prefix
@@ -23,4 +26,7 @@ pub use lib::patch;
 pub use lib::preprocessor;
 pub use lib::attention;
 pub use lib::block;
-pub use lib::transformer;
\ No newline at end of file
+pub use lib::transformer;
+pub use lib::quantized_transformer;
+pub use lib::loader;
+pub use lib::image_transform;
\ No newline at end of file
@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use tch::{nn, Kind, Tensor};
+
+const GGUF_MAGIC: u32 = 0x4655_4747; // "GGUF" little-endian
+
+/// GGML tensor element types relevant to the quantized checkpoints this
+/// loader consumes. Unlisted types are rejected rather than silently
+/// mis-decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GgmlType {
+    F32,
+    F16,
+    Q8_0,
+    Q4_0,
+}
+
+impl GgmlType {
+    fn from_u32(value: u32) -> Result<Self> {
+        match value {
+            0 => Ok(GgmlType::F32),
+            1 => Ok(GgmlType::F16),
+            2 => Ok(GgmlType::Q4_0),
+            8 => Ok(GgmlType::Q8_0),
+            other => Err(anyhow!("Unsupported GGML tensor type id: {other}")),
+        }
+    }
+
+    /// Bytes in one storage block of this type (a single element for the
+    /// float types, `elements_per_block` elements for the quantized types).
+    fn block_size(self) -> usize {
+        match self {
+            GgmlType::F32 => 4,
+            GgmlType::F16 => 2,
+            // 32 4-bit weights + one fp16 scale, packed per block.
+            GgmlType::Q4_0 => 18,
+            // 32 int8 weights + one fp16 scale, packed per block.
+            GgmlType::Q8_0 => 34,
+        }
+    }
+
+    fn elements_per_block(self) -> usize {
+        match self {
+            GgmlType::F32 | GgmlType::F16 => 1,
+            GgmlType::Q4_0 | GgmlType::Q8_0 => 32,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct GgufTensorInfo {
+    name: String,
+    dims: Vec<i64>,
+    ggml_type: GgmlType,
+    offset: u64,
+}
+
+/// Minimal GGUF container reader: header, metadata key/value table and
+/// tensor directory, enough to map named tensors onto an `AIMv2Model`
+/// `VarStore` without depending on a full GGML runtime.
+struct GgufFile {
+    tensors: Vec<GgufTensorInfo>,
+    data: Vec<u8>,
+}
+
+fn read_u32(cursor: &mut Cursor<&[u8]>) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    cursor.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(cursor: &mut Cursor<&[u8]>) -> Result<u64> {
+    let mut buf = [0u8; 8];
+    cursor.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_string(cursor: &mut Cursor<&[u8]>) -> Result<String> {
+    let len = read_u64(cursor)? as usize;
+    let mut buf = vec![0u8; len];
+    cursor.read_exact(&mut buf)?;
+    String::from_utf8(buf).context("GGUF string is not valid UTF-8")
+}
+
+/// GGUF metadata values are self-describing; we only need to skip over them
+/// since the shapes we care about come from the tensor directory instead.
+fn skip_metadata_value(cursor: &mut Cursor<&[u8]>) -> Result<()> {
+    let value_type = read_u32(cursor)?;
+    skip_value_payload(cursor, value_type)
+}
+
+/// Skips the payload of a value whose type is already known — either the
+/// type tag just read by `skip_metadata_value`, or (recursively) an array
+/// element, which carries no type tag of its own.
+fn skip_value_payload(cursor: &mut Cursor<&[u8]>, value_type: u32) -> Result<()> {
+    match value_type {
+        0 | 1 | 7 => {
+            cursor.seek(SeekFrom::Current(1))?; // uint8 / int8 / bool
+        }
+        2 | 3 => {
+            cursor.seek(SeekFrom::Current(2))?; // uint16 / int16
+        }
+        4 | 5 | 6 => {
+            cursor.seek(SeekFrom::Current(4))?; // uint32 / int32 / float32
+        }
+        10 | 11 | 12 => {
+            cursor.seek(SeekFrom::Current(8))?; // uint64 / int64 / float64
+        }
+        8 => {
+            read_string(cursor)?; // string
+        }
+        9 => {
+            // array: element type followed by count, then `count` untagged
+            // elements of that type back-to-back.
+            let elem_type = read_u32(cursor)?;
+            let count = read_u64(cursor)?;
+            for _ in 0..count {
+                skip_value_payload(cursor, elem_type)?;
+            }
+        }
+        other => return Err(anyhow!("Unknown GGUF metadata value type: {other}")),
+    }
+    Ok(())
+}
+
+impl GgufFile {
+    fn parse(bytes: Vec<u8>) -> Result<Self> {
+        let mut cursor = Cursor::new(bytes.as_slice());
+
+        let magic = read_u32(&mut cursor)?;
+        if magic != GGUF_MAGIC {
+            return Err(anyhow!("Not a GGUF file (bad magic: {magic:#x})"));
+        }
+        let _version = read_u32(&mut cursor)?;
+        let tensor_count = read_u64(&mut cursor)?;
+        let metadata_kv_count = read_u64(&mut cursor)?;
+
+        for _ in 0..metadata_kv_count {
+            read_string(&mut cursor)?; // key
+            skip_metadata_value(&mut cursor)?;
+        }
+
+        let mut tensors = Vec::with_capacity(tensor_count as usize);
+        for _ in 0..tensor_count {
+            let name = read_string(&mut cursor)?;
+            let n_dims = read_u32(&mut cursor)?;
+            let mut dims = Vec::with_capacity(n_dims as usize);
+            for _ in 0..n_dims {
+                dims.push(read_u64(&mut cursor)? as i64);
+            }
+            // GGUF stores dims fastest-varying first; tch expects the usual
+            // row-major (slowest-varying first) order.
+            dims.reverse();
+            let ggml_type = GgmlType::from_u32(read_u32(&mut cursor)?)?;
+            let offset = read_u64(&mut cursor)?;
+            tensors.push(GgufTensorInfo {
+                name,
+                dims,
+                ggml_type,
+                offset,
+            });
+        }
+
+        // Tensor data starts at the next 32-byte aligned position.
+        let data_start = (cursor.position() as usize + 31) / 32 * 32;
+        let data = bytes[data_start..].to_vec();
+
+        Ok(Self { tensors, data })
+    }
+}
+
+/// Dequantizes one tensor's raw bytes into an f32 `Tensor` with the given
+/// shape, widening block-quantized types (`Q4_0`, `Q8_0`) to full precision.
+fn dequantize_tensor(info: &GgufTensorInfo, data: &[u8]) -> Result<Tensor> {
+    let num_elements: i64 = info.dims.iter().product();
+    let num_blocks = num_elements as usize / info.ggml_type.elements_per_block();
+    let block_bytes = info.ggml_type.block_size();
+
+    let start = info.offset as usize;
+    let end = start + num_blocks * block_bytes;
+    let bytes = data
+        .get(start..end)
+        .ok_or_else(|| anyhow!("Tensor '{}' data out of bounds in GGUF file", info.name))?;
+
+    let floats: Vec<f32> = match info.ggml_type {
+        GgmlType::F32 => bytes
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+            .collect(),
+        GgmlType::F16 => bytes
+            .chunks_exact(2)
+            .map(|c| half::f16::from_le_bytes(c.try_into().unwrap()).to_f32())
+            .collect(),
+        GgmlType::Q8_0 => bytes
+            .chunks_exact(block_bytes)
+            .flat_map(|block| {
+                let scale = half::f16::from_le_bytes([block[0], block[1]]).to_f32();
+                block[2..].iter().map(move |&q| (q as i8) as f32 * scale)
+            })
+            .collect(),
+        GgmlType::Q4_0 => bytes
+            .chunks_exact(block_bytes)
+            .flat_map(|block| {
+                let scale = half::f16::from_le_bytes([block[0], block[1]]).to_f32();
+                let packed = &block[2..18];
+                // ggml Q4_0 layout: element `j` is the low nibble of
+                // `packed[j]`, element `j + 16` is the high nibble of the
+                // same byte (for j in 0..16) — not two nibbles per byte
+                // packed consecutively.
+                (0..32).map(move |j| {
+                    let byte = packed[j % 16];
+                    let nibble = if j < 16 { byte & 0x0f } else { byte >> 4 };
+                    (nibble as f32 - 8.0) * scale
+                })
+            })
+            .collect(),
+    };
+
+    Tensor::from_slice(&floats)
+        .reshape(&info.dims)
+        .to_kind(Kind::Float)
+        .f_to_kind(Kind::Float)
+        .context("Failed to materialize dequantized GGUF tensor")
+}
+
+/// Loads a GGUF/GGML-style container and copies each named tensor onto the
+/// matching variable in `vs` (e.g. `trunk.blocks.0.attn.qkv.weight`),
+/// dequantizing quantized tensor types into the VarStore's float kind.
+///
+/// This is an alternative to `VarStore::load`, which only understands
+/// safetensors/PyTorch containers.
+pub fn load_gguf(vs: &nn::VarStore, path: &Path) -> Result<()> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("Failed to read GGUF file: {:?}", path))?;
+    let gguf = GgufFile::parse(bytes)?;
+
+    let variables: HashMap<String, Tensor> = vs.variables();
+    tch::no_grad(|| -> Result<()> {
+        for info in &gguf.tensors {
+            let target = variables
+                .get(&info.name)
+                .ok_or_else(|| anyhow!("No VarStore variable named '{}'", info.name))?;
+            let source = dequantize_tensor(info, &gguf.data)?.to_device(target.device());
+            target.f_copy_(&source).with_context(|| {
+                format!(
+                    "Shape mismatch loading '{}': VarStore expects {:?}, GGUF has {:?}",
+                    info.name,
+                    target.size(),
+                    info.dims
+                )
+            })?;
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_u32(buf: &mut Vec<u8>, v: u32) {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn push_u64(buf: &mut Vec<u8>, v: u64) {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn push_string(buf: &mut Vec<u8>, s: &str) {
+        push_u64(buf, s.len() as u64);
+        buf.extend_from_slice(s.as_bytes());
+    }
+
+    /// A metadata array value (type 9) is the case `skip_metadata_value` used
+    /// to desync on: its elements carry no type tag of their own. This file
+    /// has one array-typed key before the tensor directory, so a successful
+    /// parse here exercises the fix.
+    #[test]
+    fn parses_header_with_array_metadata() {
+        let mut buf = Vec::new();
+        push_u32(&mut buf, GGUF_MAGIC);
+        push_u32(&mut buf, 3); // version
+        push_u64(&mut buf, 1); // tensor_count
+        push_u64(&mut buf, 1); // metadata_kv_count
+
+        push_string(&mut buf, "arr");
+        push_u32(&mut buf, 9); // value_type: array
+        push_u32(&mut buf, 4); // elem_type: uint32
+        push_u64(&mut buf, 3); // count
+        push_u32(&mut buf, 10);
+        push_u32(&mut buf, 20);
+        push_u32(&mut buf, 30);
+
+        push_string(&mut buf, "t");
+        push_u32(&mut buf, 1); // n_dims
+        push_u64(&mut buf, 4); // dims[0]
+        push_u32(&mut buf, 0); // ggml_type: F32
+        push_u64(&mut buf, 0); // offset
+
+        let data_start = (buf.len() + 31) / 32 * 32;
+        buf.resize(data_start, 0);
+        let values: [f32; 4] = [1.0, 2.0, 3.0, 4.0];
+        for v in values {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+
+        let gguf = GgufFile::parse(buf).expect("array metadata should not desync the parser");
+        assert_eq!(gguf.tensors.len(), 1);
+        assert_eq!(gguf.tensors[0].name, "t");
+        assert_eq!(gguf.tensors[0].dims, vec![4]);
+        assert_eq!(gguf.tensors[0].ggml_type, GgmlType::F32);
+        assert_eq!(gguf.tensors[0].offset, 0);
+
+        let tensor = dequantize_tensor(&gguf.tensors[0], &gguf.data).unwrap();
+        let round_tripped: Vec<f64> = (0..4).map(|i| tensor.double_value(&[i])).collect();
+        assert_eq!(round_tripped, vec![1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn dequantizes_q8_0_block() {
+        let scale = half::f16::from_f32(2.0);
+        let values: [i8; 32] = std::array::from_fn(|i| i as i8 - 16);
+
+        let mut block = Vec::new();
+        block.extend_from_slice(&scale.to_le_bytes());
+        block.extend(values.iter().map(|&v| v as u8));
+
+        let info = GgufTensorInfo {
+            name: "q8".to_string(),
+            dims: vec![32],
+            ggml_type: GgmlType::Q8_0,
+            offset: 0,
+        };
+        let tensor = dequantize_tensor(&info, &block).unwrap();
+        for (j, &v) in values.iter().enumerate() {
+            let expected = v as f64 * 2.0;
+            assert!((tensor.double_value(&[j as i64]) - expected).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn dequantizes_q4_0_block_with_ggml_nibble_order() {
+        // packed[i]'s low nibble is element i, high nibble is element i + 16.
+        let packed: [u8; 16] = std::array::from_fn(|i| ((15 - i as u8) << 4) | i as u8);
+        let scale = half::f16::from_f32(1.0);
+
+        let mut block = Vec::new();
+        block.extend_from_slice(&scale.to_le_bytes());
+        block.extend_from_slice(&packed);
+
+        let info = GgufTensorInfo {
+            name: "q4".to_string(),
+            dims: vec![32],
+            ggml_type: GgmlType::Q4_0,
+            offset: 0,
+        };
+        let tensor = dequantize_tensor(&info, &block).unwrap();
+
+        for i in 0..16i64 {
+            let expected_low = i as f64 - 8.0;
+            assert!((tensor.double_value(&[i]) - expected_low).abs() < 1e-3);
+            let expected_high = (15 - i) as f64 - 8.0;
+            assert!((tensor.double_value(&[i + 16]) - expected_high).abs() < 1e-3);
+        }
+    }
+}
@@ -1,103 +1,40 @@
-use anyhow::{Result, Context};
+use anyhow::{Context, Result};
 use tch::{
-    nn::{self, Module, ModuleT}, // Added ModuleT
-    Kind, Tensor, Device, vision
+    nn::{self, ModuleT},
+    Tensor, Device
 };
 
-use std::path::Path;
-
-// use aimv2_model_rs::norm::{RMSNorm};
 use aimv2_model_rs::utils::{auto_device};
-// use aimv2_model_rs::ffn::{AIMv2SwiGLUFFN};
 use aimv2_model_rs::config::{AIMv2Config};
-// use aimv2_model_rs::attention::{AIMv2Attention};
-
 use aimv2_model_rs::preprocessor::{AIMv2ViTPreprocessor};
+use aimv2_model_rs::transformer::{AIMv2Transformer, ForwardFeaturesOptions, ForwardFeaturesOutput};
+use aimv2_model_rs::quantized_transformer::{QuantScheme, QuantizedAIMv2Transformer};
+use aimv2_model_rs::loader::load_gguf;
+use aimv2_model_rs::image_transform::load_image;
 
-// use aimv2_model_rs::block::{AIMv2Block};
-
-use aimv2_model_rs::transformer::{AIMv2Transformer};
-
-
-// /// Transformer Block for AIMv2.
-// #[derive(Debug)]
-// pub struct AIMv2Block {
-//     attn: AIMv2Attention,
-//     norm_1: RMSNorm,
-//     mlp: AIMv2SwiGLUFFN,
-//     norm_2: RMSNorm,
-// }
-
-// impl AIMv2Block {
-//     pub fn new(vs: &nn::Path, config: &AIMv2Config) -> Self {
-//         let dim = config.hidden_size;
-//         let eps = config.rms_norm_eps;
-
-//         let attn = AIMv2Attention::new(&(vs / "attn"), config);
-//         let norm_1 = RMSNorm::new(&(vs / "norm_1"), dim, eps);
-//         let mlp = AIMv2SwiGLUFFN::new(&(vs / "mlp"), config);
-//         let norm_2 = RMSNorm::new(&(vs / "norm_2"), dim, eps);
-
-//         Self { attn, norm_1, mlp, norm_2 }
-//     }
-// }
-
-// impl nn::ModuleT for AIMv2Block {
-//     fn forward_t(&self, xs: &Tensor, train: bool) -> Tensor {
-//         let residual_1 = xs + self.attn.forward_t(&self.norm_1.forward(xs), train);
-//         residual_1.shallow_clone() + self.mlp.forward(&self.norm_2.forward(&residual_1))
-//     }
-// }
-
-
-/// The main Transformer Trunk for AIMv2.
-// #[derive(Debug)]
-// pub struct AIMv2Transformer {
-//     blocks: Vec<AIMv2Block>,
-//     post_trunk_norm: RMSNorm,
-// }
-
-// impl AIMv2Transformer {
-//     pub fn new(vs: &nn::Path, config: &AIMv2Config) -> Self {
-//         let num_hidden_layers = config.num_hidden_layers;
-//         let hidden_size = config.hidden_size;
-//         let rms_norm_eps = config.rms_norm_eps;
-
-//         let mut blocks = Vec::with_capacity(num_hidden_layers as usize);
-//         let blocks_vs = vs / "blocks"; // Path for the blocks module list
-
-//         for i in 0..num_hidden_layers {
-//             // Correctly construct path for each block: trunk.blocks.<i>
-//             let block_path = blocks_vs.clone() / i.to_string();
-//             blocks.push(AIMv2Block::new(&block_path, config));
-//         }
-
-//         let post_trunk_norm = RMSNorm::new(&(vs / "post_trunk_norm"), hidden_size, rms_norm_eps);
-
-//         Self { blocks, post_trunk_norm }
-//     }
-
-//     pub fn forward_(&self, tokens: &Tensor, train: bool) -> Tensor {
-//         let mut current_tokens = tokens.shallow_clone();
-//         for block in &self.blocks {
-//             current_tokens = block.forward_t(&current_tokens, train);
-//         }
-//         self.post_trunk_norm.forward(&current_tokens)
-//     }
-// }
-
-// impl nn::ModuleT for AIMv2Transformer {
-//     fn forward_t(&self, xs: &Tensor, train: bool) -> Tensor {
-//         self.forward_(xs, train)
-//     }
-// }
+/// Either the full-precision or the quantized trunk, selected at
+/// construction time by `AIMv2Model::new` vs. `AIMv2Model::new_quantized`.
+#[derive(Debug)]
+enum Trunk {
+    Float(AIMv2Transformer),
+    Quantized(QuantizedAIMv2Transformer),
+}
+
+impl Trunk {
+    fn forward_t(&self, xs: &Tensor, train: bool) -> Tensor {
+        match self {
+            Trunk::Float(trunk) => trunk.forward_t(xs, train),
+            Trunk::Quantized(trunk) => trunk.forward_t(xs, train),
+        }
+    }
+}
 
 // --- Main Model ---
 /// The complete AIMv2 Model.
 #[derive(Debug)]
 pub struct AIMv2Model {
     preprocessor: AIMv2ViTPreprocessor,
-    trunk: AIMv2Transformer,
+    trunk: Trunk,
 }
 
 impl AIMv2Model {
@@ -105,18 +42,66 @@ impl AIMv2Model {
     pub fn new(vs: &nn::Path, config: &AIMv2Config) -> Self {
         let preprocessor = AIMv2ViTPreprocessor::new(&(vs / "preprocessor"), config);
         let trunk = AIMv2Transformer::new(&(vs / "trunk"), config);
-        Self { preprocessor, trunk }
+        Self {
+            preprocessor,
+            trunk: Trunk::Float(trunk),
+        }
+    }
+
+    /// Creates a new AIMv2 Model whose trunk projections are quantized
+    /// per `scheme`, for running the large/huge checkpoints on smaller GPUs.
+    /// Weights still need to be filled in via `load_quantized`.
+    pub fn new_quantized(vs: &nn::Path, config: &AIMv2Config, scheme: QuantScheme) -> Self {
+        let preprocessor = AIMv2ViTPreprocessor::new(&(vs / "preprocessor"), config);
+        let trunk = QuantizedAIMv2Transformer::new(&(vs / "trunk"), config, scheme);
+        Self {
+            preprocessor,
+            trunk: Trunk::Quantized(trunk),
+        }
+    }
+
+    /// Fills in a quantized trunk's weights from a packed safetensors
+    /// checkpoint (see `QuantizedAIMv2Transformer::load_quantized_safetensors`).
+    /// Errors if this model was built with `new` instead of `new_quantized`.
+    pub fn load_quantized(&mut self, vs: &nn::VarStore, path: &std::path::Path) -> Result<()> {
+        let Trunk::Quantized(trunk) = &mut self.trunk else {
+            anyhow::bail!("load_quantized called on a non-quantized model");
+        };
+        trunk.load_quantized_safetensors(vs, path)
     }
 }
 
 impl nn::ModuleT for AIMv2Model {
     /// Forward pass for the AIMv2 Model. Takes pixel_values (NCHW).
     fn forward_t(&self, pixel_values: &Tensor, train: bool) -> Tensor {
+        use tch::nn::Module;
         let tokens = self.preprocessor.forward(pixel_values);
         self.trunk.forward_t(&tokens, train)
     }
 }
 
+impl AIMv2Model {
+    /// Like `forward_t`, but can additionally collect per-block hidden
+    /// states and attention maps (see `ForwardFeaturesOptions`), for probing,
+    /// feature-pyramid use of ViT layers, and attention visualization.
+    ///
+    /// Only supported on the full-precision trunk; quantized models are
+    /// built for fast pooled inference, not feature probing.
+    pub fn forward_features(
+        &self,
+        pixel_values: &Tensor,
+        train: bool,
+        opts: ForwardFeaturesOptions,
+    ) -> Result<ForwardFeaturesOutput> {
+        use tch::nn::Module;
+        let Trunk::Float(trunk) = &self.trunk else {
+            anyhow::bail!("forward_features is not supported on a quantized trunk");
+        };
+        let tokens = self.preprocessor.forward(pixel_values);
+        Ok(trunk.forward_features(&tokens, train, opts))
+    }
+}
+
 // --- Main Function ---
 fn main() -> Result<()> {
     // Set device
@@ -126,15 +111,12 @@ fn main() -> Result<()> {
     let image_path = "/Users/andrewmayes/Dev/aimv2-large-patch14-native/coco_cat.jpg"; // Make sure this image exists or change the path
     let config = AIMv2Config::aimv2_large_patch14(); // Or the appropriate config
 
-    // 1. Load Image
-    let original_img = vision::image::load(image_path)
-        .with_context(|| format!("Failed to load image: {}", image_path))?;
-    
-
-    // 4. Add batch dimension and move to device
-    let input_tensor = original_img.unsqueeze(0).to_device(device).to_kind(Kind::Float);
+    // 1. Load, resize, center-crop and normalize the image
+    let input_tensor = load_image(std::path::Path::new(image_path), &config)
+        .with_context(|| format!("Failed to preprocess image: {}", image_path))?
+        .to_device(device);
     println!("Final input tensor shape: {:?}, device: {:?}", input_tensor.size(), input_tensor.device());
-    
+
 
     // --- Model Loading and Inference ---
     let mut vs = nn::VarStore::new(device);
@@ -145,8 +127,13 @@ fn main() -> Result<()> {
 
     let weights_path = "/Users/andrewmayes/Dev/AIMv2-rs/model/model.safetensors"; // Adjust path if needed
     println!("Loading weights from: {}", weights_path);
-    vs.load(weights_path)
-        .with_context(|| format!("Failed to load weights from '{}'", weights_path))?;
+    if weights_path.ends_with(".gguf") {
+        load_gguf(&vs, std::path::Path::new(weights_path))
+            .with_context(|| format!("Failed to load GGUF weights from '{}'", weights_path))?;
+    } else {
+        vs.load(weights_path)
+            .with_context(|| format!("Failed to load weights from '{}'", weights_path))?;
+    }
     println!("Weights loaded successfully.");
 
     vs.freeze(); // Set to evaluation mode
@@ -165,4 +152,4 @@ fn main() -> Result<()> {
     println!("Rust output saved.");
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,23 @@
+use tch::{nn, nn::Module, Tensor};
+
+/// Root-mean-square layer norm, as used by AIMv2 in place of `LayerNorm`.
+#[derive(Debug)]
+pub struct RMSNorm {
+    weight: Tensor,
+    eps: f64,
+}
+
+impl RMSNorm {
+    pub fn new(vs: &nn::Path, dim: i64, eps: f64) -> Self {
+        let weight = vs.ones("weight", &[dim]);
+        Self { weight, eps }
+    }
+}
+
+impl Module for RMSNorm {
+    fn forward(&self, xs: &Tensor) -> Tensor {
+        let variance = xs.pow_tensor_scalar(2).mean_dim(-1, true, xs.kind());
+        let normed = xs * (variance + self.eps).rsqrt();
+        normed * &self.weight
+    }
+}
@@ -0,0 +1,38 @@
+use tch::{nn, nn::Module, Tensor};
+
+use crate::config::AIMv2Config;
+use crate::norm::RMSNorm;
+
+/// Splits an NCHW image into non-overlapping patches and projects each patch
+/// to `hidden_size`, via a strided convolution.
+#[derive(Debug)]
+pub struct AIMv2PatchEmbed {
+    proj: nn::Conv2D,
+    norm: RMSNorm,
+}
+
+impl AIMv2PatchEmbed {
+    pub fn new(vs: &nn::Path, config: &AIMv2Config) -> Self {
+        let conv_cfg = nn::ConvConfig {
+            stride: config.patch_size,
+            ..Default::default()
+        };
+        let proj = nn::conv2d(
+            vs / "proj",
+            config.num_channels,
+            config.hidden_size,
+            config.patch_size,
+            conv_cfg,
+        );
+        let norm = RMSNorm::new(&(vs / "norm"), config.hidden_size, config.rms_norm_eps);
+
+        Self { proj, norm }
+    }
+}
+
+impl Module for AIMv2PatchEmbed {
+    fn forward(&self, xs: &Tensor) -> Tensor {
+        let patches = self.proj.forward(xs).flatten(2, 3).transpose(1, 2);
+        self.norm.forward(&patches)
+    }
+}
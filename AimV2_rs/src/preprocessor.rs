@@ -0,0 +1,31 @@
+use tch::{nn, nn::Module, Tensor};
+
+use crate::config::AIMv2Config;
+use crate::embed::AIMv2PositionalEmbedding;
+use crate::patch::AIMv2PatchEmbed;
+
+/// Turns a preprocessed NCHW pixel tensor into the token sequence consumed by
+/// the `AIMv2Transformer` trunk: patchify, then add positional embeddings.
+#[derive(Debug)]
+pub struct AIMv2ViTPreprocessor {
+    patchifier: AIMv2PatchEmbed,
+    pos_embed: AIMv2PositionalEmbedding,
+}
+
+impl AIMv2ViTPreprocessor {
+    pub fn new(vs: &nn::Path, config: &AIMv2Config) -> Self {
+        let patchifier = AIMv2PatchEmbed::new(&(vs / "patchifier"), config);
+        let pos_embed = AIMv2PositionalEmbedding::new(&(vs / "pos_embed"), config);
+        Self {
+            patchifier,
+            pos_embed,
+        }
+    }
+}
+
+impl Module for AIMv2ViTPreprocessor {
+    fn forward(&self, xs: &Tensor) -> Tensor {
+        let tokens = self.patchifier.forward(xs);
+        self.pos_embed.forward(&tokens)
+    }
+}
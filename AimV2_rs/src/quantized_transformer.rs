@@ -0,0 +1,511 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use safetensors::SafeTensors;
+use tch::{nn, nn::ModuleT, Kind, Tensor};
+
+use crate::config::AIMv2Config;
+use crate::norm::RMSNorm;
+
+/// Quantization scheme used by `QuantLinear`.
+///
+/// `Int8` holds one (scale, zero_point) pair per output channel, with
+/// `qweight` stored one int8 value per element. `Int4` additionally groups
+/// the input dimension into `group_size`-wide blocks, each with its own
+/// scale/zero-point, and packs two 4-bit elements per `qweight` byte (the
+/// low nibble holds element `2k`, the high nibble element `2k + 1`).
+#[derive(Debug, Clone, Copy)]
+pub enum QuantScheme {
+    Int8,
+    Int4 { group_size: i64 },
+}
+
+/// A quantized drop-in replacement for `nn::Linear`.
+///
+/// `scale` and `zero_point` (and `bias`) are ordinary VarStore variables, so
+/// a plain `vs.load(path)` fills them in exactly like the float model's
+/// `nn::Linear` weights. `qweight` is *not* a VarStore variable — tch's
+/// `VarStore` only manages float tensors, and this is packed int8 data — so
+/// it is loaded separately by `QuantizedAIMv2Transformer::load_quantized_safetensors`.
+#[derive(Debug)]
+pub struct QuantLinear {
+    qweight: Tensor,
+    scale: Tensor,
+    zero_point: Tensor,
+    bias: Option<Tensor>,
+    scheme: QuantScheme,
+    in_features: i64,
+    out_features: i64,
+}
+
+impl QuantLinear {
+    /// Allocates a quantized linear under `vs`. `qweight` starts zeroed and
+    /// is filled in later by `load_qweight`; `scale`/`zero_point`/`bias` are
+    /// registered in the VarStore like any other float parameter.
+    pub fn new(
+        vs: &nn::Path,
+        in_features: i64,
+        out_features: i64,
+        bias: bool,
+        scheme: QuantScheme,
+    ) -> Self {
+        let device = vs.device();
+        let packed_width = match scheme {
+            QuantScheme::Int8 => in_features,
+            // Two 4-bit elements packed per byte.
+            QuantScheme::Int4 { .. } => (in_features + 1) / 2,
+        };
+        let qweight = Tensor::zeros(&[out_features, packed_width], (Kind::Uint8, device));
+
+        let num_groups = match scheme {
+            QuantScheme::Int8 => 1,
+            QuantScheme::Int4 { group_size } => (in_features + group_size - 1) / group_size,
+        };
+        let scale = vs.ones("scale", &[out_features, num_groups]);
+        let zero_point = vs.zeros("zero_point", &[out_features, num_groups]);
+        let bias = bias.then(|| vs.zeros("bias", &[out_features]));
+
+        Self {
+            qweight,
+            scale,
+            zero_point,
+            bias,
+            scheme,
+            in_features,
+            out_features,
+        }
+    }
+
+    /// Copies this layer's packed `qweight` tensor (named `<prefix>.qweight`
+    /// in the quantized checkpoint) into its buffer. `scale`/`zero_point`/
+    /// `bias` are loaded separately, via the VarStore's own `vs.load`.
+    fn load_qweight(&mut self, tensors: &SafeTensors, prefix: &str) -> Result<()> {
+        self.qweight =
+            read_tensor(tensors, &format!("{prefix}.qweight"))?.to_device(self.scale.device());
+        Ok(())
+    }
+
+    /// Dequantizes the packed weight back to a `[out_features, in_features]`
+    /// fp32 tensor, expanding per-group scale/zero-point and unpacking `Int4`
+    /// nibbles as needed.
+    fn dequantize(&self) -> Tensor {
+        match self.scheme {
+            QuantScheme::Int8 => {
+                let qweight = self.qweight.to_kind(Kind::Float);
+                (qweight - &self.zero_point) * &self.scale
+            }
+            QuantScheme::Int4 { group_size } => {
+                let packed = self.qweight.to_kind(Kind::Int32);
+                let low = &packed - (&packed / 16) * 16;
+                let high = &packed / 16;
+                let qweight = Tensor::stack(&[low, high], -1)
+                    .reshape(&[self.out_features, -1])
+                    .narrow(1, 0, self.in_features)
+                    .to_kind(Kind::Float);
+
+                let scale = self
+                    .scale
+                    .repeat_interleave_self_int(group_size, 1, None)
+                    .narrow(1, 0, self.in_features);
+                let zero_point = self
+                    .zero_point
+                    .repeat_interleave_self_int(group_size, 1, None)
+                    .narrow(1, 0, self.in_features);
+                (qweight - zero_point) * scale
+            }
+        }
+    }
+}
+
+impl ModuleT for QuantLinear {
+    fn forward_t(&self, xs: &Tensor, _train: bool) -> Tensor {
+        let weight = self.dequantize();
+        let out = xs.matmul(&weight.transpose(0, 1));
+        match &self.bias {
+            Some(bias) => out + bias,
+            None => out,
+        }
+    }
+}
+
+/// Quantized multi-head self-attention, mirroring `AIMv2Attention` except
+/// that it always normalizes with standard softmax: `config.quiet_softmax`
+/// is not applied here.
+#[derive(Debug)]
+pub struct QuantizedAIMv2Attention {
+    qkv: QuantLinear,
+    proj: QuantLinear,
+    num_heads: i64,
+    head_dim: i64,
+}
+
+impl QuantizedAIMv2Attention {
+    pub fn new(vs: &nn::Path, config: &AIMv2Config, scheme: QuantScheme) -> Self {
+        let dim = config.hidden_size;
+        let num_heads = config.num_attention_heads;
+        let head_dim = dim / num_heads;
+
+        let qkv = QuantLinear::new(&(vs / "qkv"), dim, dim * 3, config.qkv_bias, scheme);
+        let proj = QuantLinear::new(&(vs / "proj"), dim, dim, config.use_bias, scheme);
+
+        Self {
+            qkv,
+            proj,
+            num_heads,
+            head_dim,
+        }
+    }
+}
+
+impl ModuleT for QuantizedAIMv2Attention {
+    fn forward_t(&self, xs: &Tensor, train: bool) -> Tensor {
+        let (batch, seq_len, dim) = xs.size3().unwrap();
+
+        let qkv = self
+            .qkv
+            .forward_t(xs, train)
+            .reshape(&[batch, seq_len, 3, self.num_heads, self.head_dim])
+            .permute(&[2, 0, 3, 1, 4]);
+        let q = qkv.select(0, 0);
+        let k = qkv.select(0, 1);
+        let v = qkv.select(0, 2);
+
+        let scale = (self.head_dim as f64).powf(-0.5);
+        let scores = q.matmul(&k.transpose(-2, -1)) * scale;
+        let attn = scores.softmax(-1, Kind::Float);
+
+        let out = attn
+            .matmul(&v)
+            .transpose(1, 2)
+            .contiguous()
+            .reshape(&[batch, seq_len, dim]);
+
+        self.proj.forward_t(&out, train)
+    }
+}
+
+impl QuantizedAIMv2Attention {
+    fn load_qweights(&mut self, tensors: &SafeTensors, prefix: &str) -> Result<()> {
+        self.qkv.load_qweight(tensors, &format!("{prefix}.qkv"))?;
+        self.proj.load_qweight(tensors, &format!("{prefix}.proj"))?;
+        Ok(())
+    }
+}
+
+/// Quantized SwiGLU feed-forward block, mirroring `AIMv2SwiGLUFFN`.
+#[derive(Debug)]
+pub struct QuantizedAIMv2SwiGLUFFN {
+    fc1: QuantLinear,
+    fc2: QuantLinear,
+    fc3: QuantLinear,
+}
+
+impl QuantizedAIMv2SwiGLUFFN {
+    pub fn new(vs: &nn::Path, config: &AIMv2Config, scheme: QuantScheme) -> Self {
+        let in_dim = config.hidden_size;
+        let hidden_dim = config.intermediate_size;
+
+        let fc1 = QuantLinear::new(&(vs / "fc1"), in_dim, hidden_dim, config.use_bias, scheme);
+        let fc2 = QuantLinear::new(&(vs / "fc2"), hidden_dim, in_dim, config.use_bias, scheme);
+        let fc3 = QuantLinear::new(&(vs / "fc3"), in_dim, hidden_dim, config.use_bias, scheme);
+
+        Self { fc1, fc2, fc3 }
+    }
+}
+
+impl ModuleT for QuantizedAIMv2SwiGLUFFN {
+    fn forward_t(&self, xs: &Tensor, train: bool) -> Tensor {
+        let gate = self.fc1.forward_t(xs, train).silu();
+        let value = self.fc3.forward_t(xs, train);
+        self.fc2.forward_t(&(gate * value), train)
+    }
+}
+
+impl QuantizedAIMv2SwiGLUFFN {
+    fn load_qweights(&mut self, tensors: &SafeTensors, prefix: &str) -> Result<()> {
+        self.fc1.load_qweight(tensors, &format!("{prefix}.fc1"))?;
+        self.fc2.load_qweight(tensors, &format!("{prefix}.fc2"))?;
+        self.fc3.load_qweight(tensors, &format!("{prefix}.fc3"))?;
+        Ok(())
+    }
+}
+
+/// Quantized transformer block, mirroring `AIMv2Block`.
+#[derive(Debug)]
+pub struct QuantizedAIMv2Block {
+    attn: QuantizedAIMv2Attention,
+    norm_1: RMSNorm,
+    mlp: QuantizedAIMv2SwiGLUFFN,
+    norm_2: RMSNorm,
+}
+
+impl QuantizedAIMv2Block {
+    pub fn new(vs: &nn::Path, config: &AIMv2Config, scheme: QuantScheme) -> Self {
+        let dim = config.hidden_size;
+        let eps = config.rms_norm_eps;
+
+        let attn = QuantizedAIMv2Attention::new(&(vs / "attn"), config, scheme);
+        let norm_1 = RMSNorm::new(&(vs / "norm_1"), dim, eps);
+        let mlp = QuantizedAIMv2SwiGLUFFN::new(&(vs / "mlp"), config, scheme);
+        let norm_2 = RMSNorm::new(&(vs / "norm_2"), dim, eps);
+
+        Self {
+            attn,
+            norm_1,
+            mlp,
+            norm_2,
+        }
+    }
+}
+
+impl ModuleT for QuantizedAIMv2Block {
+    fn forward_t(&self, xs: &Tensor, train: bool) -> Tensor {
+        use tch::nn::Module;
+        let residual_1 = xs + self.attn.forward_t(&self.norm_1.forward(xs), train);
+        residual_1.shallow_clone() + self.mlp.forward_t(&self.norm_2.forward(&residual_1), train)
+    }
+}
+
+impl QuantizedAIMv2Block {
+    fn load_qweights(&mut self, tensors: &SafeTensors, prefix: &str) -> Result<()> {
+        self.attn.load_qweights(tensors, &format!("{prefix}.attn"))?;
+        self.mlp.load_qweights(tensors, &format!("{prefix}.mlp"))?;
+        Ok(())
+    }
+}
+
+/// Quantized transformer trunk, mirroring `AIMv2Transformer`. Only the
+/// `nn::Linear` projections inside attention and the FFN are quantized; the
+/// RMSNorm layers stay in fp32 since they are cheap and sensitive to
+/// precision loss.
+#[derive(Debug)]
+pub struct QuantizedAIMv2Transformer {
+    blocks: Vec<QuantizedAIMv2Block>,
+    post_trunk_norm: RMSNorm,
+}
+
+impl QuantizedAIMv2Transformer {
+    pub fn new(vs: &nn::Path, config: &AIMv2Config, scheme: QuantScheme) -> Self {
+        let blocks_vs = vs / "blocks";
+        let blocks = (0..config.num_hidden_layers)
+            .map(|i| {
+                let block_path = blocks_vs.clone() / i.to_string();
+                QuantizedAIMv2Block::new(&block_path, config, scheme)
+            })
+            .collect();
+
+        let post_trunk_norm = RMSNorm::new(
+            &(vs / "post_trunk_norm"),
+            config.hidden_size,
+            config.rms_norm_eps,
+        );
+
+        Self {
+            blocks,
+            post_trunk_norm,
+        }
+    }
+}
+
+impl ModuleT for QuantizedAIMv2Transformer {
+    fn forward_t(&self, xs: &Tensor, train: bool) -> Tensor {
+        use tch::nn::Module;
+        let mut current_tokens = xs.shallow_clone();
+        for block in &self.blocks {
+            current_tokens = block.forward_t(&current_tokens, train);
+        }
+        self.post_trunk_norm.forward(&current_tokens)
+    }
+}
+
+impl QuantizedAIMv2Transformer {
+    /// Loads a complete quantized checkpoint: first `vs.load(path)` fills in
+    /// every float VarStore variable (RMSNorm weights, and each
+    /// `QuantLinear`'s `scale`/`zero_point`/`bias`), then the packed int8
+    /// `qweight` tensors — which live outside the VarStore — are copied in
+    /// separately from the same file.
+    pub fn load_quantized_safetensors(&mut self, vs: &nn::VarStore, path: &Path) -> Result<()> {
+        vs.load(path)
+            .with_context(|| format!("Failed to load quantized checkpoint: {:?}", path))?;
+
+        let data = std::fs::read(path)
+            .with_context(|| format!("Failed to read quantized checkpoint: {:?}", path))?;
+        let tensors = SafeTensors::deserialize(&data)
+            .with_context(|| format!("Failed to parse safetensors header: {:?}", path))?;
+
+        for (i, block) in self.blocks.iter_mut().enumerate() {
+            block.load_qweights(&tensors, &format!("blocks.{i}"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads a named tensor out of a safetensors file into a CPU `tch::Tensor`
+/// of the matching kind; `QuantLinear::load_qweight` then moves it to the
+/// layer's device.
+fn read_tensor(tensors: &SafeTensors, name: &str) -> Result<Tensor> {
+    let view = tensors
+        .tensor(name)
+        .with_context(|| format!("Missing tensor '{name}' in quantized checkpoint"))?;
+    Tensor::from_data_size(
+        view.data(),
+        &view.shape().iter().map(|&d| d as i64).collect::<Vec<_>>(),
+        safetensors_kind_to_tch(view.dtype())?,
+    )
+    .f_totype(safetensors_kind_to_tch(view.dtype())?)
+    .with_context(|| format!("Failed to materialize tensor '{name}'"))
+}
+
+fn safetensors_kind_to_tch(dtype: safetensors::Dtype) -> Result<Kind> {
+    match dtype {
+        safetensors::Dtype::F32 => Ok(Kind::Float),
+        safetensors::Dtype::F16 => Ok(Kind::Half),
+        safetensors::Dtype::BF16 => Ok(Kind::BFloat16),
+        safetensors::Dtype::I8 => Ok(Kind::Int8),
+        safetensors::Dtype::U8 => Ok(Kind::Uint8),
+        safetensors::Dtype::I64 => Ok(Kind::Int64),
+        other => anyhow::bail!("Unsupported safetensors dtype for quantized checkpoint: {other:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use safetensors::tensor::TensorView;
+    use std::collections::HashMap;
+
+    const MARKER: f32 = 0.42;
+    const PACKED_MARKER: u8 = 0xAB;
+
+    fn tiny_config() -> AIMv2Config {
+        AIMv2Config {
+            hidden_size: 4,
+            num_hidden_layers: 1,
+            num_attention_heads: 2,
+            intermediate_size: 8,
+            num_channels: 3,
+            image_size: 14,
+            patch_size: 14,
+            rms_norm_eps: 1e-5,
+            qkv_bias: false,
+            use_bias: false,
+            quiet_softmax: false,
+            image_mean: [0.5, 0.5, 0.5],
+            image_std: [0.5, 0.5, 0.5],
+        }
+    }
+
+    fn packed_width(scheme: QuantScheme, in_features: i64) -> i64 {
+        match scheme {
+            QuantScheme::Int8 => in_features,
+            QuantScheme::Int4 { .. } => (in_features + 1) / 2,
+        }
+    }
+
+    /// Builds a synthetic checkpoint covering every VarStore float variable
+    /// (RMSNorm weights and each `QuantLinear`'s scale/zero_point) plus the
+    /// packed `qweight` tensors that live outside the VarStore, so
+    /// `load_quantized_safetensors` has a real int8/int4 round trip to run,
+    /// not just an all-zeros trunk.
+    fn build_checkpoint(vs: &nn::VarStore, config: &AIMv2Config, scheme: QuantScheme) -> Vec<u8> {
+        let variables = vs.variables();
+        let mut float_bufs: Vec<(String, Vec<usize>, Vec<u8>)> = Vec::new();
+        for (name, tensor) in &variables {
+            let shape: Vec<usize> = tensor.size().iter().map(|&d| d as usize).collect();
+            let numel: usize = shape.iter().product();
+            let mut bytes = Vec::with_capacity(numel * 4);
+            for _ in 0..numel {
+                bytes.extend_from_slice(&MARKER.to_le_bytes());
+            }
+            float_bufs.push((name.clone(), shape, bytes));
+        }
+
+        let dim = config.hidden_size;
+        let hidden_dim = config.intermediate_size;
+        let linear_shapes = [
+            ("attn.qkv", dim, dim * 3),
+            ("attn.proj", dim, dim),
+            ("mlp.fc1", dim, hidden_dim),
+            ("mlp.fc2", hidden_dim, dim),
+            ("mlp.fc3", dim, hidden_dim),
+        ];
+        let mut packed_bufs: Vec<(String, Vec<usize>, Vec<u8>)> = Vec::new();
+        for i in 0..config.num_hidden_layers {
+            for (suffix, in_features, out_features) in linear_shapes {
+                let width = packed_width(scheme, in_features) as usize;
+                let name = format!("blocks.{i}.{suffix}.qweight");
+                let bytes = vec![PACKED_MARKER; out_features as usize * width];
+                packed_bufs.push((name, vec![out_features as usize, width], bytes));
+            }
+        }
+
+        let mut views = HashMap::new();
+        for (name, shape, bytes) in &float_bufs {
+            views.insert(
+                name.clone(),
+                TensorView::new(safetensors::Dtype::F32, shape.clone(), bytes).unwrap(),
+            );
+        }
+        for (name, shape, bytes) in &packed_bufs {
+            views.insert(
+                name.clone(),
+                TensorView::new(safetensors::Dtype::U8, shape.clone(), bytes).unwrap(),
+            );
+        }
+
+        safetensors::serialize(&views, &None).unwrap()
+    }
+
+    fn assert_round_trip(scheme: QuantScheme, file_suffix: &str) {
+        let config = tiny_config();
+        let vs = nn::VarStore::new(tch::Device::Cpu);
+        let mut trunk = QuantizedAIMv2Transformer::new(&vs.root(), &config, scheme);
+
+        let bytes = build_checkpoint(&vs, &config, scheme);
+        let path = std::env::temp_dir().join(format!(
+            "aimv2_rs_quant_{file_suffix}_{}.safetensors",
+            std::process::id()
+        ));
+        std::fs::write(&path, &bytes).unwrap();
+        trunk.load_quantized_safetensors(&vs, &path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Every float VarStore variable (RMSNorm weights, scale, zero_point)
+        // should have picked up the checkpoint's marker value, not stayed at
+        // its `new`-time default (ones for norm weight/scale, zeros for
+        // zero_point).
+        for (name, tensor) in vs.variables() {
+            let first = tensor.flatten(0, -1).double_value(&[0]);
+            assert!(
+                (first - MARKER as f64).abs() < 1e-5,
+                "variable '{name}' was not loaded from the checkpoint (value {first})"
+            );
+        }
+
+        // The packed qweight buffers live outside the VarStore, so they need
+        // their own check that `load_quantized_safetensors` actually copied
+        // them in instead of leaving the zero-initialized default.
+        for block in &trunk.blocks {
+            for linear in [
+                &block.attn.qkv,
+                &block.attn.proj,
+                &block.mlp.fc1,
+                &block.mlp.fc2,
+                &block.mlp.fc3,
+            ] {
+                let byte = linear.qweight.int64_value(&[0, 0]);
+                assert_eq!(byte, PACKED_MARKER as i64);
+            }
+        }
+    }
+
+    #[test]
+    fn load_quantized_safetensors_round_trips_int8() {
+        assert_round_trip(QuantScheme::Int8, "int8");
+    }
+
+    #[test]
+    fn load_quantized_safetensors_round_trips_int4() {
+        assert_round_trip(QuantScheme::Int4 { group_size: 2 }, "int4");
+    }
+}
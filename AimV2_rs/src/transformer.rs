@@ -0,0 +1,103 @@
+use tch::{nn, nn::Module, nn::ModuleT, Tensor};
+
+use crate::block::AIMv2Block;
+use crate::config::AIMv2Config;
+use crate::norm::RMSNorm;
+
+/// The main Transformer Trunk for AIMv2.
+#[derive(Debug)]
+pub struct AIMv2Transformer {
+    blocks: Vec<AIMv2Block>,
+    post_trunk_norm: RMSNorm,
+}
+
+impl AIMv2Transformer {
+    pub fn new(vs: &nn::Path, config: &AIMv2Config) -> Self {
+        let num_hidden_layers = config.num_hidden_layers;
+        let hidden_size = config.hidden_size;
+        let rms_norm_eps = config.rms_norm_eps;
+
+        let mut blocks = Vec::with_capacity(num_hidden_layers as usize);
+        let blocks_vs = vs / "blocks"; // Path for the blocks module list
+
+        for i in 0..num_hidden_layers {
+            // Correctly construct path for each block: trunk.blocks.<i>
+            let block_path = blocks_vs.clone() / i.to_string();
+            blocks.push(AIMv2Block::new(&block_path, config));
+        }
+
+        let post_trunk_norm = RMSNorm::new(&(vs / "post_trunk_norm"), hidden_size, rms_norm_eps);
+
+        Self {
+            blocks,
+            post_trunk_norm,
+        }
+    }
+
+    pub fn forward_(&self, tokens: &Tensor, train: bool) -> Tensor {
+        let mut current_tokens = tokens.shallow_clone();
+        for block in &self.blocks {
+            current_tokens = block.forward_t(&current_tokens, train);
+        }
+        self.post_trunk_norm.forward(&current_tokens)
+    }
+
+    /// Runs the trunk like `forward_`, but can additionally collect each
+    /// block's hidden state and attention map, for probing and attention
+    /// visualization use cases that a single output tensor can't serve.
+    pub fn forward_features(&self, tokens: &Tensor, train: bool, opts: ForwardFeaturesOptions) -> ForwardFeaturesOutput {
+        let mut current_tokens = tokens.shallow_clone();
+        let mut hidden_states = opts.output_hidden_states.then(Vec::new);
+        let mut attentions = opts.output_attentions.then(Vec::new);
+
+        if let Some(hidden_states) = &mut hidden_states {
+            hidden_states.push(current_tokens.shallow_clone());
+        }
+
+        for block in &self.blocks {
+            let (out, attn) =
+                block.forward_t_with_attn(&current_tokens, train, opts.output_attentions);
+            current_tokens = out;
+
+            if let Some(hidden_states) = &mut hidden_states {
+                hidden_states.push(current_tokens.shallow_clone());
+            }
+            if let (Some(attentions), Some(attn)) = (&mut attentions, attn) {
+                attentions.push(attn);
+            }
+        }
+
+        ForwardFeaturesOutput {
+            last_hidden_state: self.post_trunk_norm.forward(&current_tokens),
+            hidden_states,
+            attentions,
+        }
+    }
+}
+
+/// Switches for `AIMv2Transformer::forward_features`, mirroring the
+/// `output_hidden_states` / `output_attentions` flags on Hugging Face models.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ForwardFeaturesOptions {
+    pub output_hidden_states: bool,
+    pub output_attentions: bool,
+}
+
+/// Result of `AIMv2Transformer::forward_features` / `AIMv2Model::forward_features`.
+#[derive(Debug)]
+pub struct ForwardFeaturesOutput {
+    /// The final `post_trunk_norm` output, same as plain `forward_t`.
+    pub last_hidden_state: Tensor,
+    /// Hidden states before block 0 and after every block, present when
+    /// `output_hidden_states` is set (`num_hidden_layers + 1` entries, as in
+    /// Hugging Face's `output_hidden_states`).
+    pub hidden_states: Option<Vec<Tensor>>,
+    /// Per-block attention probabilities, present when `output_attentions` is set.
+    pub attentions: Option<Vec<Tensor>>,
+}
+
+impl ModuleT for AIMv2Transformer {
+    fn forward_t(&self, xs: &Tensor, train: bool) -> Tensor {
+        self.forward_(xs, train)
+    }
+}
@@ -0,0 +1,10 @@
+use tch::Device;
+
+/// Picks the best available device: CUDA if present, otherwise CPU.
+pub fn auto_device() -> Device {
+    if tch::Cuda::is_available() {
+        Device::Cuda(0)
+    } else {
+        Device::Cpu
+    }
+}